@@ -1,29 +1,47 @@
-use std::{
+use core::{
     ascii::escape_default,
-    fmt,
-    io::{self, BufRead, Read, Write},
     error,
+    fmt,
+};
+
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::String,
+    vec::Vec,
 };
 
 use rand::Rng;
 
+// the engine never touches `std` directly; programs read input and write
+// output through these two traits so the interpreter can be embedded in
+// `no_std`/alloc-only hosts. the `std` feature provides blanket impls over
+// the usual `BufRead`/`Write` types (see the `std_impls` module below).
+pub trait Input {
+    type Error;
+
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<(), Self::Error>;
+    fn read_line(&mut self, buf: &mut String) -> Result<(), Self::Error>;
+    fn read_byte(&mut self) -> Result<u8, Self::Error>;
+}
+
+pub trait Output {
+    type Error;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error>;
+}
+
 #[derive(Debug)]
-pub enum Error {
+pub enum Error<E> {
     InvalidCharacter(i64),
     InvalidInstruction(u8),
     InvalidNumeric(String),
-    IOError(io::Error),
+    IOError(E),
     PlayfieldTooWide,
     PlayfieldTooTall,
 }
 
-impl From<io::Error> for Error {
-    fn from(e: io::Error) -> Self {
-        Self::IOError(e)
-    }
-}
-
-impl fmt::Display for Error {
+impl<E: fmt::Display> fmt::Display for Error<E> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Error::InvalidCharacter(n) =>
@@ -42,7 +60,7 @@ impl fmt::Display for Error {
     }
 }
 
-impl error::Error for Error { }
+impl<E: fmt::Debug + fmt::Display> error::Error for Error<E> { }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Direction {
@@ -52,37 +70,76 @@ pub enum Direction {
     Right,
 }
 
+// which language dialect to execute: strict Befunge-93 (fixed 80×25 grid,
+// only the classic opcodes; `p` outside the grid is dropped) or Funge-98 (the
+// full instruction set on an unbounded grid). the grid is stored sparsely in
+// either mode.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Befunge93,
+    Funge98,
+}
+
 #[derive(Clone, Debug)]
 pub struct B93 {
-    playfield: [[u8; 80]; 25],
+    // sparse playfield: unset cells read as space, so the grid is unbounded
+    playfield: BTreeMap<(i64, i64), u8>,
     stack: Vec<i64>,
-    i: u8,
-    j: u8,
-    dir: Direction,
+    x: i64,
+    y: i64,
+    // the program-counter delta, added to (x, y) by advance_pc
+    dx: i64,
+    dy: i64,
     bridge: bool,
     string: bool,
+    mode: Mode,
+    return_code: i32,
+    // Lahey-space bounding box of the live playfield; PC wrapping folds the
+    // cursor back into this box rather than a hard-coded 80×25 grid
+    min_x: i64,
+    min_y: i64,
+    max_x: i64,
+    max_y: i64,
 }
 
 impl B93 {
     pub fn new(playfield: [[u8; 80]; 25]) -> Self {
+        let mut cells = BTreeMap::new();
+        for (i, row) in playfield.iter().enumerate() {
+            for (j, &c) in row.iter().enumerate() {
+                if c != b' ' {
+                    cells.insert((j as i64, i as i64), c);
+                }
+            }
+        }
         Self {
-            playfield,
+            playfield: cells,
             stack: Vec::new(),
-            i: 0,
-            j: 0,
-            dir: Direction::Right,
+            x: 0,
+            y: 0,
+            dx: 1,
+            dy: 0,
             bridge: false,
             string: false,
+            mode: Mode::Befunge93,
+            return_code: 0,
+            min_x: 0,
+            min_y: 0,
+            max_x: 79,
+            max_y: 24,
         }
     }
 
-    pub fn from_stream<R: Read>(rdr: &mut R) -> Result<Self, Error> {
+    pub fn from_stream<R: Input>(rdr: &mut R, mode: Mode
+      ) -> Result<Self, Error<R::Error>> {
         let mut buf = Vec::new();
-        rdr.read_to_end(&mut buf)?;
+        rdr.read_to_end(&mut buf).map_err(Error::IOError)?;
 
-        let mut playfield = [[b' '; 80]; 25];
-        let mut i = 0;
-        let mut j = 0;
+        let mut playfield = BTreeMap::new();
+        let mut i: i64 = 0;
+        let mut j: i64 = 0;
+        let mut max_x: i64 = 0;
+        let mut max_y: i64 = 0;
         let mut maybe_crlf = false;
         for b in buf {
             if maybe_crlf && b == b'\n' {
@@ -100,28 +157,101 @@ impl B93 {
                 continue;
             }
 
-            if i >= 25 {
-                return Err(Error::PlayfieldTooWide);
-            }
+            // Befunge-93 is strict about the 80×25 grid; Funge-98 is unbounded
+            if mode == Mode::Befunge93 {
+                if i >= 25 {
+                    return Err(Error::PlayfieldTooWide);
+                }
 
-            if j >= 80 {
-                return Err(Error::PlayfieldTooTall);
+                if j >= 80 {
+                    return Err(Error::PlayfieldTooTall);
+                }
             }
 
-            playfield[i][j] = b;
+            if b != b' ' {
+                playfield.insert((j, i), b);
+            }
+            if j > max_x {
+                max_x = j;
+            }
+            if i > max_y {
+                max_y = i;
+            }
             j += 1;
         }
 
-        Ok(Self::new(playfield))
+        // Befunge-93 always sees the full 80×25 board regardless of content
+        if mode == Mode::Befunge93 {
+            max_x = 79;
+            max_y = 24;
+        }
+
+        Ok(Self {
+            playfield,
+            stack: Vec::new(),
+            x: 0,
+            y: 0,
+            dx: 1,
+            dy: 0,
+            bridge: false,
+            string: false,
+            mode,
+            return_code: 0,
+            min_x: 0,
+            min_y: 0,
+            max_x,
+            max_y,
+        })
     }
 
     pub fn next_instruction(&self) -> u8 {
-        self.playfield[self.i as usize][self.j as usize]
+        self.cell(self.x, self.y)
+    }
+
+    pub fn position(&self) -> (i64, i64) {
+        (self.x, self.y)
+    }
+
+    pub fn direction(&self) -> Direction {
+        match (self.dx, self.dy) {
+            (0, dy) if dy < 0 => Direction::Up,
+            (0, _) => Direction::Down,
+            (dx, _) if dx < 0 => Direction::Left,
+            _ => Direction::Right,
+        }
+    }
+
+    pub fn stack(&self) -> &[i64] {
+        &self.stack
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn return_code(&self) -> i32 {
+        self.return_code
+    }
+
+    // the live value of a cell, honoring the unbounded coordinate space.
+    //
+    // note: `(x, y)` is `(column, row)`, matching the PC orientation and the
+    // standard Funge convention. the original Befunge-93 engine indexed
+    // `playfield[x][y]` with `x` as the *row*, i.e. transposed relative to
+    // `next_instruction`; the sparse rewrite fixes that. this is an
+    // intentional behavior change for 93 programs that use `g`/`p`.
+    pub fn cell(&self, x: i64, y: i64) -> u8 {
+        self.playfield.get(&(x, y)).copied().unwrap_or(b' ')
+    }
+
+    // the Lahey-space bounding box, as (min_x, min_y, max_x, max_y)
+    pub fn bounds(&self) -> (i64, i64, i64, i64) {
+        (self.min_x, self.min_y, self.max_x, self.max_y)
     }
 
     pub fn step<R, W, Rand>(&mut self, rdr: &mut R, wtr: &mut W, rng: &mut Rand
-      ) -> Result<Option<()>, Error>
-      where R: BufRead, W: Write, Rand: Rng {
+      ) -> Result<Option<()>, Error<R::Error>>
+      where R: Input, W: Output<Error = R::Error>, Rand: Rng {
         if self.bridge {
             self.bridge = false;
             self.advance_pc();
@@ -137,21 +267,37 @@ impl B93 {
             return Ok(Some(()));
         }
 
-        match self.next_instruction() {
+        let op = self.next_instruction();
+        if self.execute(op, rdr, wtr, rng)?.is_none() {
+            return Ok(None);
+        }
+        self.advance_pc();
+        Ok(Some(()))
+    }
+
+    // execute a single opcode in place, without the trailing PC advance or
+    // handling the bridge/string modes (those belong to step); `'`, `j` and
+    // `k` move the PC over the cells they consume. returns Ok(None) to halt.
+    fn execute<R, W, Rand>(&mut self, op: u8, rdr: &mut R, wtr: &mut W,
+      rng: &mut Rand) -> Result<Option<()>, Error<R::Error>>
+      where R: Input, W: Output<Error = R::Error>, Rand: Rng {
+        match op {
             b' ' => { },
             b'@' => return Ok(None),
-            b'^' => self.dir = Direction::Up,
-            b'v' => self.dir = Direction::Down,
-            b'<' => self.dir = Direction::Left,
-            b'>' => self.dir = Direction::Right,
+            b'^' => { self.dx = 0; self.dy = -1; },
+            b'v' => { self.dx = 0; self.dy = 1; },
+            b'<' => { self.dx = -1; self.dy = 0; },
+            b'>' => { self.dx = 1; self.dy = 0; },
             b'?' => {
-                self.dir = match rng.gen_range(0..4) {
-                    0 => Direction::Up,
-                    1 => Direction::Down,
-                    2 => Direction::Left,
-                    3 => Direction::Right,
+                let (dx, dy) = match rng.gen_range(0..4) {
+                    0 => (0, -1),
+                    1 => (0, 1),
+                    2 => (-1, 0),
+                    3 => (1, 0),
                     _ => panic!("impossible RNG result"),
-                }
+                };
+                self.dx = dx;
+                self.dy = dy;
             },
             b'"' => self.string = true,
             b'+' => {
@@ -187,23 +333,25 @@ impl B93 {
                 }
             },
             b'_' => {
+                self.dy = 0;
                 if self.pop() != 0 {
-                    self.dir = Direction::Left;
+                    self.dx = -1;
                 } else {
-                    self.dir = Direction::Right;
+                    self.dx = 1;
                 }
             },
             b'|' => {
+                self.dx = 0;
                 if self.pop() != 0 {
-                    self.dir = Direction::Up;
+                    self.dy = -1;
                 } else {
-                    self.dir = Direction::Down;
+                    self.dy = 1;
                 }
             },
             b'&' => {
                 // spec unclear; I'm saying this must be line-buffered
                 let mut buf = String::new();
-                rdr.read_line(&mut buf)?;
+                rdr.read_line(&mut buf).map_err(Error::IOError)?;
                 if let Ok(val) = buf.trim().parse() {
                     self.push(val);
                 } else {
@@ -211,20 +359,20 @@ impl B93 {
                 }
             },
             b'~' => {
-                let mut buf = [0u8];
-                rdr.read_exact(&mut buf)?;
-                self.push(buf[0] as i64);
+                let val = rdr.read_byte().map_err(Error::IOError)?;
+                self.push(val as i64);
             },
-            b'.' => write!(wtr, "{} ", self.pop())?,
+            b'.' => wtr.write_str(&format!("{} ", self.pop()))
+              .map_err(Error::IOError)?,
             b',' => {
                 let val = self.pop();
-                if val < 0 || val > 127 {
+                if !(0..=127).contains(&val) {
                     return Err(Error::InvalidCharacter(val));
                 }
                 // safety: val is a valid u8 due to range check above
                 // (also a valid ASCII char)
-                write!(wtr, "{}",
-                  TryInto::<u8>::try_into(val).unwrap() as char)?
+                let c = TryInto::<u8>::try_into(val).unwrap() as char;
+                wtr.write_str(&format!("{}", c)).map_err(Error::IOError)?
             },
             b'#' => self.bridge = true,
             b':' => self.push(self.peek()),
@@ -248,38 +396,140 @@ impl B93 {
             b'g' => {
                 let y = self.pop();
                 let x = self.pop();
-                if y < 0 || y >= 80 || x < 0 || x >= 25 {
-                    self.push(b' ' as i64);
-                } else {
-                    self.push(self.playfield[x as usize][y as usize] as i64);
-                }
+                self.push(self.cell(x, y) as i64);
             },
             b'p' => {
                 let y = self.pop();
                 let x = self.pop();
                 let val = self.pop();
-                if y >= 0 && y < 80 && x >= 0 && x < 25 {
-                    // unclear what to do if i64 out of bounds for u8?
-                    self.playfield[x as usize][y as usize] = val as u8;
+                // unclear what to do if i64 out of bounds for u8?
+                self.set(x, y, val as u8);
+            },
+            // Funge-98 extensions; rejected as invalid under strict Befunge-93
+            b'\'' if self.mode == Mode::Funge98 => {
+                // fetch the next cell as a value and skip over it
+                self.advance_pc();
+                self.push(self.next_instruction() as i64);
+            },
+            b'j' if self.mode == Mode::Funge98 => {
+                let n = self.pop();
+                if n >= 0 {
+                    for _ in 0..n {
+                        self.advance_pc();
+                    }
+                } else {
+                    self.reverse();
+                    for _ in 0..n.unsigned_abs() {
+                        self.advance_pc();
+                    }
+                    self.reverse();
+                }
+            },
+            b'k' if self.mode == Mode::Funge98 => {
+                let n = self.pop();
+                self.advance_pc();
+                let op = self.next_instruction();
+                for _ in 0..n {
+                    if self.execute(op, rdr, wtr, rng)?.is_none() {
+                        return Ok(None);
+                    }
+                }
+            },
+            b'x' if self.mode == Mode::Funge98 => {
+                let dy = self.pop();
+                let dx = self.pop();
+                self.dx = dx;
+                self.dy = dy;
+            },
+            b'[' if self.mode == Mode::Funge98 => {
+                // turn the delta left 90°
+                let (dx, dy) = (self.dx, self.dy);
+                self.dx = dy;
+                self.dy = -dx;
+            },
+            b']' if self.mode == Mode::Funge98 => {
+                // turn the delta right 90°
+                let (dx, dy) = (self.dx, self.dy);
+                self.dx = -dy;
+                self.dy = dx;
+            },
+            b'r' if self.mode == Mode::Funge98 => self.reverse(),
+            b'n' if self.mode == Mode::Funge98 => self.stack.clear(),
+            b'q' if self.mode == Mode::Funge98 => {
+                self.return_code = self.pop() as i32;
+                return Ok(None);
+            },
+            b'w' if self.mode == Mode::Funge98 => {
+                let b = self.pop();
+                let a = self.pop();
+                if a < b {
+                    // turn left
+                    let (dx, dy) = (self.dx, self.dy);
+                    self.dx = dy;
+                    self.dy = -dx;
+                } else if a > b {
+                    // turn right
+                    let (dx, dy) = (self.dx, self.dy);
+                    self.dx = -dy;
+                    self.dy = dx;
                 }
             },
             b => return Err(Error::InvalidInstruction(b)),
         };
-        self.advance_pc();
         Ok(Some(()))
     }
 
     fn advance_pc(&mut self) {
-        match self.dir {
-            Direction::Up =>
-                self.i = if self.i == 0 { 24 } else { self.i - 1 },
-            Direction::Down =>
-                self.i = (self.i + 1) % 25,
-            Direction::Left =>
-                self.j = if self.j == 0 { 79 } else { self.j - 1 },
-            Direction::Right =>
-                self.j = (self.j + 1) % 80,
-        };
+        if self.dx == 0 && self.dy == 0 {
+            return;
+        }
+
+        self.x += self.dx;
+        self.y += self.dy;
+
+        if !self.in_bounds(self.x, self.y) {
+            // Lahey-space wrap: back up along -delta to the far edge
+            let (mut x, mut y) = (self.x, self.y);
+            while self.in_bounds(x - self.dx, y - self.dy) {
+                x -= self.dx;
+                y -= self.dy;
+            }
+            self.x = x;
+            self.y = y;
+        }
+    }
+
+    fn reverse(&mut self) {
+        self.dx = -self.dx;
+        self.dy = -self.dy;
+    }
+
+    fn in_bounds(&self, x: i64, y: i64) -> bool {
+        x >= self.min_x && x <= self.max_x && y >= self.min_y && y <= self.max_y
+    }
+
+    fn set(&mut self, x: i64, y: i64, val: u8) {
+        // strict Befunge-93 keeps the fixed 80×25 grid: out-of-range `p`
+        // writes are dropped and the Lahey box never grows, so the classic
+        // mod-80/mod-25 PC wrap in advance_pc stays intact. only Funge-98
+        // honors the unbounded coordinate space.
+        if self.mode == Mode::Befunge93
+          && (!(0..80).contains(&x) || !(0..25).contains(&y)) {
+            return;
+        }
+
+        if val == b' ' {
+            self.playfield.remove(&(x, y));
+        } else {
+            self.playfield.insert((x, y), val);
+        }
+
+        if self.mode == Mode::Funge98 {
+            if x < self.min_x { self.min_x = x; }
+            if x > self.max_x { self.max_x = x; }
+            if y < self.min_y { self.min_y = y; }
+            if y > self.max_y { self.max_y = y; }
+        }
     }
 
     fn push(&mut self, val: i64) {
@@ -301,3 +551,293 @@ impl Default for B93 {
         Self::new([[b' '; 80]; 25])
     }
 }
+
+// checkpointing lives behind the `std` feature: the running `B93` *is* the
+// complete machine state (playfield, stack, cursor and flags), so serializing
+// it to a byte stream and reading it back is enough to pause and resume a run
+// — self-modified cells and all.
+#[cfg(feature = "std")]
+mod snapshot {
+    use std::io::{self, ErrorKind, Read, Write};
+
+    use alloc::collections::BTreeMap;
+
+    use super::{B93, Mode};
+
+    // "B93S", then a one-byte format version
+    const MAGIC: [u8; 4] = *b"B93S";
+    const VERSION: u8 = 1;
+
+    fn write_i64<W: Write>(w: &mut W, val: i64) -> io::Result<()> {
+        w.write_all(&val.to_le_bytes())
+    }
+
+    fn read_i64<R: Read>(r: &mut R) -> io::Result<i64> {
+        let mut buf = [0u8; 8];
+        r.read_exact(&mut buf)?;
+        Ok(i64::from_le_bytes(buf))
+    }
+
+    impl B93 {
+        pub fn save<W: Write>(&self, w: &mut W) -> io::Result<()> {
+            w.write_all(&MAGIC)?;
+            w.write_all(&[VERSION])?;
+            w.write_all(&[match self.mode {
+                Mode::Befunge93 => 0,
+                Mode::Funge98 => 1,
+            }])?;
+            w.write_all(&[self.bridge as u8, self.string as u8])?;
+            write_i64(w, self.x)?;
+            write_i64(w, self.y)?;
+            write_i64(w, self.dx)?;
+            write_i64(w, self.dy)?;
+            write_i64(w, self.min_x)?;
+            write_i64(w, self.min_y)?;
+            write_i64(w, self.max_x)?;
+            write_i64(w, self.max_y)?;
+            w.write_all(&self.return_code.to_le_bytes())?;
+
+            write_i64(w, self.stack.len() as i64)?;
+            for &val in &self.stack {
+                write_i64(w, val)?;
+            }
+
+            write_i64(w, self.playfield.len() as i64)?;
+            for (&(x, y), &val) in &self.playfield {
+                write_i64(w, x)?;
+                write_i64(w, y)?;
+                w.write_all(&[val])?;
+            }
+
+            Ok(())
+        }
+
+        pub fn load<R: Read>(r: &mut R) -> io::Result<Self> {
+            let mut magic = [0u8; 4];
+            r.read_exact(&mut magic)?;
+            if magic != MAGIC {
+                return Err(io::Error::new(ErrorKind::InvalidData,
+                  "not a b93 snapshot"));
+            }
+
+            let mut byte = [0u8];
+            r.read_exact(&mut byte)?;
+            if byte[0] != VERSION {
+                return Err(io::Error::new(ErrorKind::InvalidData,
+                  "unsupported snapshot version"));
+            }
+
+            r.read_exact(&mut byte)?;
+            let mode = match byte[0] {
+                0 => Mode::Befunge93,
+                1 => Mode::Funge98,
+                _ => return Err(io::Error::new(ErrorKind::InvalidData,
+                  "unknown execution mode")),
+            };
+
+            let mut flags = [0u8; 2];
+            r.read_exact(&mut flags)?;
+            let bridge = flags[0] != 0;
+            let string = flags[1] != 0;
+
+            let x = read_i64(r)?;
+            let y = read_i64(r)?;
+            let dx = read_i64(r)?;
+            let dy = read_i64(r)?;
+            let min_x = read_i64(r)?;
+            let min_y = read_i64(r)?;
+            let max_x = read_i64(r)?;
+            let max_y = read_i64(r)?;
+
+            let mut code = [0u8; 4];
+            r.read_exact(&mut code)?;
+            let return_code = i32::from_le_bytes(code);
+
+            let stack_len = read_i64(r)?;
+            let mut stack = alloc::vec::Vec::with_capacity(stack_len.max(0) as usize);
+            for _ in 0..stack_len {
+                stack.push(read_i64(r)?);
+            }
+
+            let cells = read_i64(r)?;
+            let mut playfield = BTreeMap::new();
+            for _ in 0..cells {
+                let x = read_i64(r)?;
+                let y = read_i64(r)?;
+                r.read_exact(&mut byte)?;
+                playfield.insert((x, y), byte[0]);
+            }
+
+            Ok(Self {
+                playfield,
+                stack,
+                x,
+                y,
+                dx,
+                dy,
+                bridge,
+                string,
+                mode,
+                return_code,
+                min_x,
+                min_y,
+                max_x,
+                max_y,
+            })
+        }
+    }
+}
+
+// blanket impls bridging the std I/O traits onto our endpoints; only present
+// when the default `std` feature is enabled.
+#[cfg(feature = "std")]
+mod std_impls {
+    use std::io::{self, BufRead, Read, Write};
+
+    use alloc::{string::String, vec::Vec};
+
+    use super::{Input, Output};
+
+    impl<R: BufRead> Input for R {
+        type Error = io::Error;
+
+        fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<(), Self::Error> {
+            Read::read_to_end(self, buf)?;
+            Ok(())
+        }
+
+        fn read_line(&mut self, buf: &mut String) -> Result<(), Self::Error> {
+            BufRead::read_line(self, buf)?;
+            Ok(())
+        }
+
+        fn read_byte(&mut self) -> Result<u8, Self::Error> {
+            let mut buf = [0u8];
+            self.read_exact(&mut buf)?;
+            Ok(buf[0])
+        }
+    }
+
+    impl<W: Write> Output for W {
+        type Error = io::Error;
+
+        fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+            self.write_all(s.as_bytes())
+        }
+    }
+}
+
+// Funge-98 behavior checks: the extended instruction set and the Lahey-space
+// wrap only exist in 98 mode, so these drive short programs through `step`
+// and observe the cursor, delta, stack, and playfield. run under the default
+// `std` feature, which supplies the blanket I/O impls used by the harness.
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // run `src` in Funge-98 mode for at most `steps` iterations (stopping
+    // early on halt) and return the resulting machine. input is empty and
+    // output is discarded; the PRNG is seeded so `?` stays deterministic.
+    fn run98(src: &str, steps: usize) -> B93 {
+        let mut m = B93::from_stream(
+          &mut Cursor::new(src.as_bytes().to_vec()), Mode::Funge98).unwrap();
+        let mut inp = Cursor::new(Vec::new());
+        let mut out: Vec<u8> = Vec::new();
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..steps {
+            if m.step(&mut inp, &mut out, &mut rng).unwrap().is_none() {
+                break;
+            }
+        }
+        m
+    }
+
+    #[test]
+    fn q_sets_return_code() {
+        // '\'A' pushes 'A' (65), then q pops it as the exit status
+        let m = run98("'Aq", 5);
+        assert_eq!(m.return_code(), 65);
+    }
+
+    #[test]
+    fn reverse_flips_the_delta() {
+        assert_eq!(run98("r", 1).direction(), Direction::Left);
+    }
+
+    #[test]
+    fn turn_left_and_right() {
+        assert_eq!(run98("[", 1).direction(), Direction::Up);
+        assert_eq!(run98("]", 1).direction(), Direction::Down);
+    }
+
+    #[test]
+    fn x_sets_the_delta_from_a_vector() {
+        // build (dx, dy) = (0, 1) on the stack, then `x` pops it into the
+        // delta, steering the PC downward
+        let m = run98("'a!'a'b-x", 6);
+        assert_eq!(m.direction(), Direction::Down);
+    }
+
+    #[test]
+    fn p_and_g_honor_the_unbounded_grid() {
+        // store 'Z' (90) at the out-of-range cell (100, 65), then read it
+        // back with g; the sparse grid grows to include the cell
+        let m = run98("'Z'd'Ap'd'Ag", 7);
+        assert_eq!(m.cell(100, 65), 90);
+        assert_eq!(m.stack(), &[90]);
+        let (_, _, max_x, max_y) = m.bounds();
+        assert!(max_x >= 100 && max_y >= 65);
+    }
+
+    #[test]
+    fn pc_wraps_across_the_lahey_box() {
+        // a four-wide field of spaces: four rightward steps wrap the PC back
+        // to the left edge
+        assert_eq!(run98("    ", 4).position(), (0, 0));
+    }
+}
+
+// snapshot save/load is pure (de)serialization, so the thing to pin down is
+// the round-trip: a self-modified, mid-run machine must come back byte-for-
+// byte. only present with `std`, since save/load live behind that feature.
+#[cfg(all(test, feature = "std"))]
+mod snapshot_tests {
+    use super::*;
+    use std::io::Cursor;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn save_load_round_trips() {
+        // drive a Funge-98 program that self-modifies the grid with `p`,
+        // leaves a value on the stack, and quits with a return code, so the
+        // snapshot has to carry every piece of state.
+        let mut m = B93::from_stream(
+          &mut Cursor::new(b"'Z'd'Ap'X'Yq".to_vec()), Mode::Funge98).unwrap();
+        let mut inp = Cursor::new(Vec::new());
+        let mut out: Vec<u8> = Vec::new();
+        let mut rng = StdRng::seed_from_u64(0);
+        while let Some(()) = m.step(&mut inp, &mut out, &mut rng).unwrap() { }
+
+        // sanity: the run actually moved the machine off its defaults
+        assert_eq!(m.cell(100, 65), 90);
+        assert_eq!(m.return_code(), 89);
+        assert_eq!(m.stack(), &[88]);
+
+        let mut buf = Vec::new();
+        m.save(&mut buf).unwrap();
+        let back = B93::load(&mut Cursor::new(buf)).unwrap();
+
+        // compare field-by-field: the playfield (with its self-modified
+        // cell), stack, cursor/delta, mode, flags, bounds, and return code
+        assert_eq!(back.playfield, m.playfield);
+        assert_eq!(back.stack, m.stack);
+        assert_eq!((back.x, back.y), (m.x, m.y));
+        assert_eq!((back.dx, back.dy), (m.dx, m.dy));
+        assert_eq!(back.mode, m.mode);
+        assert_eq!((back.bridge, back.string), (m.bridge, m.string));
+        assert_eq!(back.bounds(), m.bounds());
+        assert_eq!(back.return_code, m.return_code);
+    }
+}
@@ -0,0 +1,202 @@
+/*
+Copyright © 2023 Derrick W. Turk
+
+Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+associated documentation files (the “Software”), to deal in the Software without restriction,
+including without limitation the rights to use, copy, modify, merge, publish, distribute,
+sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all copies or
+substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT
+OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use std::{
+    ascii::escape_default,
+    collections::HashSet,
+    error::Error,
+    io::{self, BufRead, Write},
+};
+
+use rand::Rng;
+
+use b93::b93::{B93, Direction};
+
+// the rendered viewport size, matching the classic 80×25 Befunge-93 grid
+const VIEW_COLS: i64 = 80;
+const VIEW_ROWS: i64 = 25;
+
+// a single-step debugger driving the `B93::step` loop. because `p` rewrites
+// the playfield at runtime, every redraw reads live from the engine rather
+// than caching any part of the grid.
+pub struct Debugger {
+    breakpoints: HashSet<(i64, i64)>,
+    max_steps: Option<u64>,
+    steps: u64,
+}
+
+// a command parsed from the REPL prompt; the REPL keeps looping on breakpoint
+// edits and only surfaces one of these to the stepping loop.
+enum Command {
+    Step,
+    Continue,
+    Quit,
+}
+
+impl Debugger {
+    pub fn new() -> Self {
+        Self {
+            breakpoints: HashSet::new(),
+            max_steps: None,
+            steps: 0,
+        }
+    }
+
+    pub fn set_max_steps(&mut self, max: u64) {
+        self.max_steps = Some(max);
+    }
+
+    pub fn add_breakpoint(&mut self, x: i64, y: i64) {
+        self.breakpoints.insert((x, y));
+    }
+
+    pub fn remove_breakpoint(&mut self, x: i64, y: i64) -> bool {
+        self.breakpoints.remove(&(x, y))
+    }
+
+    // true when execution should pause before the next step: either the PC
+    // sits on a breakpoint cell, or we have run out our step budget.
+    fn paused(&self, b93: &B93) -> bool {
+        self.breakpoints.contains(&b93.position())
+          || self.max_steps.is_some_and(|max| self.steps >= max)
+    }
+
+    // render the playfield (at most an 80×25 window) with the PC cell replaced
+    // by its direction glyph, the stack top-to-bottom, and the pending
+    // instruction.
+    pub fn render<V: Write>(&self, b93: &B93, view: &mut V) -> io::Result<()> {
+        let (cx, cy) = b93.position();
+        let glyph = match b93.direction() {
+            Direction::Up => b'^',
+            Direction::Down => b'v',
+            Direction::Left => b'<',
+            Direction::Right => b'>',
+        };
+
+        // read live from the playfield across a viewport around the PC,
+        // clamped to the Lahey-space bounding box. in Befunge-93 the box is
+        // the full 80×25 grid so the whole field shows; in Funge-98 a single
+        // `p` to a distant cell can grow the box without limit, so the
+        // viewport keeps the redraw bounded rather than walking a huge rect.
+        let (min_x, min_y, max_x, max_y) = b93.bounds();
+        let x0 = min_x.max((cx - VIEW_COLS / 2).min(max_x - VIEW_COLS + 1));
+        let x1 = max_x.min(x0 + VIEW_COLS - 1);
+        let y0 = min_y.max((cy - VIEW_ROWS / 2).min(max_y - VIEW_ROWS + 1));
+        let y1 = max_y.min(y0 + VIEW_ROWS - 1);
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                let b = if x == cx && y == cy {
+                    glyph
+                } else {
+                    b93.cell(x, y)
+                };
+                let c = if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                };
+                write!(view, "{}", c)?;
+            }
+            writeln!(view)?;
+        }
+
+        writeln!(view, "stack:")?;
+        for val in b93.stack().iter().rev() {
+            writeln!(view, "  {}", val)?;
+        }
+
+        writeln!(view, "next: '{}'", escape_default(b93.next_instruction()))?;
+        Ok(())
+    }
+
+    // prompt until the user asks to step, continue, or quit; breakpoint edits
+    // are applied in place and re-prompt. EOF on the command stream quits.
+    fn prompt<C, V>(&mut self, b93: &B93, cmds: &mut C, view: &mut V
+      ) -> io::Result<Command>
+      where C: BufRead, V: Write {
+        loop {
+            self.render(b93, view)?;
+            write!(view, "(b93dbg) ")?;
+            view.flush()?;
+
+            let mut line = String::new();
+            if cmds.read_line(&mut line)? == 0 {
+                return Ok(Command::Quit);
+            }
+
+            let mut parts = line.split_whitespace();
+            match parts.next() {
+                None | Some("s") | Some("step") => return Ok(Command::Step),
+                Some("c") | Some("continue") => return Ok(Command::Continue),
+                Some("q") | Some("quit") => return Ok(Command::Quit),
+                Some(cmd @ ("b" | "break" | "d" | "delete")) => {
+                    // coordinates are (x, y) = (column, row), matching
+                    // position(); a plain `b <i> <j>` would store them
+                    // transposed and never fire on the intended cell.
+                    match (parts.next().and_then(|s| s.parse().ok()),
+                           parts.next().and_then(|s| s.parse().ok())) {
+                        (Some(x), Some(y)) => {
+                            if cmd == "b" || cmd == "break" {
+                                self.add_breakpoint(x, y);
+                            } else {
+                                self.remove_breakpoint(x, y);
+                            }
+                        },
+                        _ => writeln!(view, "usage: {} <x> <y>", cmd)?,
+                    }
+                },
+                Some(other) => writeln!(view, "unknown command: {}", other)?,
+            }
+        }
+    }
+
+    // drive the engine under user control: program input and debugger commands
+    // share `cmds`, program output goes to `out`, and the rendered view to
+    // `view` (typically stderr).
+    pub fn run<C, W, V, Rand>(&mut self, b93: &mut B93,
+      cmds: &mut C, out: &mut W, view: &mut V, rng: &mut Rand
+      ) -> Result<(), Box<dyn Error>>
+      where C: BufRead, W: Write, V: Write, Rand: Rng {
+        let mut continuing = false;
+        loop {
+            if !continuing || self.paused(b93) {
+                continuing = false;
+                match self.prompt(b93, cmds, view)? {
+                    Command::Step => { },
+                    Command::Continue => continuing = true,
+                    Command::Quit => return Ok(()),
+                }
+            }
+
+            match b93.step(cmds, out, rng)? {
+                Some(()) => self.steps += 1,
+                None => {
+                    writeln!(view, "halted after {} steps", self.steps)?;
+                    return Ok(());
+                },
+            }
+        }
+    }
+}
+
+impl Default for Debugger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,9 @@
+// the Befunge engine is no_std + alloc so it can be embedded in constrained
+// hosts; the std ergonomics (blanket I/O impls, snapshot save/load) come back
+// behind the default `std` feature. the command-line front-end lives in the
+// binary target, which requires `std`.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod b93;
@@ -17,28 +17,174 @@ DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE,
 OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 */
 
+// the command-line front-end for the `b93` engine. the engine lives in the
+// library crate (no_std + alloc); this binary requires the default `std`
+// feature for stdin/stdout, files, and process exit.
 use std::{
     env::args,
     error::Error,
-    io,
+    io::{self, BufRead, Read, Write, Cursor},
     fs::File,
+    process::exit,
 };
 
-use rand::thread_rng;
+use getopts::Options;
+use rand::{Rng, thread_rng, rngs::StdRng, SeedableRng};
 
-pub mod b93;
-use b93::B93;
+use b93::b93::{B93, Mode};
+
+mod debug;
+use debug::Debugger;
+
+fn usage(program: &str, opts: &Options) -> String {
+    let brief = format!("Usage: {} [options] [FILE]", program);
+    opts.usage(&brief)
+}
+
+// expand tabs to the next multiple of eight columns, resetting at each line
+fn expand_tabs(src: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(src.len());
+    let mut col = 0;
+    for &b in src {
+        match b {
+            b'\t' => {
+                let next = (col / 8 + 1) * 8;
+                while col < next {
+                    out.push(b' ');
+                    col += 1;
+                }
+            },
+            b'\n' | b'\r' => {
+                out.push(b);
+                col = 0;
+            },
+            _ => {
+                out.push(b);
+                col += 1;
+            },
+        }
+    }
+    out
+}
+
+fn run<R, W, Rand>(b93: &mut B93, rdr: &mut R, wtr: &mut W, rng: &mut Rand,
+  trace: bool, max_steps: Option<u64>) -> Result<(), Box<dyn Error>>
+  where R: BufRead, W: Write, Rand: Rng {
+    let mut steps: u64 = 0;
+    while !max_steps.is_some_and(|max| steps >= max) {
+        if b93.step(rdr, wtr, rng)?.is_none() {
+            break;
+        }
+        if trace {
+            let (x, y) = b93.position();
+            eprintln!("[{}] ({}, {}) {:?}", steps, x, y, b93.stack());
+        }
+        steps += 1;
+    }
+    Ok(())
+}
 
 fn main() -> Result<(), Box<dyn Error>> {
     let argv: Vec<_> = args().collect();
-    let mut b93 = match &argv[..] {
-        [_, file] => B93::from_stream(&mut File::open(file)?)?,
-        [_] => B93::from_stream(&mut io::stdin())?,
-        _ => Err("too many source files provided")?,
+    let program = argv.first().map(String::as_str).unwrap_or("b93");
+
+    let mut opts = Options::new();
+    opts.optflag("s", "stdin", "read the program from standard input");
+    opts.optflag("e", "expand-tabs", "expand tabs to spaces in the source");
+    opts.optflag("t", "trace", "dump PC and stack after each step");
+    opts.optflag("d", "debug", "run under the interactive stepping debugger");
+    opts.optflag("9", "funge98", "execute in Funge-98 mode");
+    opts.optopt("n", "max-steps", "stop after at most N steps", "N");
+    opts.optopt("", "seed", "seed the ? instruction's PRNG for determinism", "N");
+    opts.optopt("", "restore", "resume from a snapshot written by --checkpoint", "FILE");
+    opts.optopt("", "checkpoint", "write a snapshot once the run stops", "FILE");
+    opts.optflag("h", "help", "print this help text and exit");
+
+    let matches = match opts.parse(&argv[1..]) {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprint!("{}", usage(program, &opts));
+            exit(2);
+        },
+    };
+
+    if matches.opt_present("help") {
+        print!("{}", usage(program, &opts));
+        return Ok(());
+    }
+
+    let trace = matches.opt_present("trace");
+    let max_steps = matches.opt_str("max-steps")
+        .map(|s| s.parse())
+        .transpose()?;
+    let seed: Option<u64> = matches.opt_str("seed")
+        .map(|s| s.parse())
+        .transpose()?;
+
+    // a snapshot carries the whole machine state, so --restore supplants
+    // loading a program from source
+    let mut b93 = if let Some(file) = matches.opt_str("restore") {
+        B93::load(&mut File::open(file)?)?
+    } else {
+        let mut src = Vec::new();
+        if matches.opt_present("stdin") || matches.free.is_empty() {
+            io::stdin().read_to_end(&mut src)?;
+        } else if matches.free.len() == 1 {
+            File::open(&matches.free[0])?.read_to_end(&mut src)?;
+        } else {
+            eprintln!("too many source files provided");
+            eprint!("{}", usage(program, &opts));
+            exit(2);
+        }
+
+        if matches.opt_present("expand-tabs") {
+            src = expand_tabs(&src);
+        }
+
+        let mode = if matches.opt_present("funge98") {
+            Mode::Funge98
+        } else {
+            Mode::Befunge93
+        };
+
+        B93::from_stream(&mut Cursor::new(src), mode)?
     };
     let mut inp = io::stdin().lock();
     let mut out = io::stdout();
-    let mut rng = thread_rng();
-    while let Some(()) = b93.step(&mut inp, &mut out, &mut rng)? { }
+
+    if matches.opt_present("debug") {
+        let mut dbg = Debugger::new();
+        if let Some(max) = max_steps {
+            dbg.set_max_steps(max);
+        }
+        let mut view = io::stderr();
+        match seed {
+            Some(s) => dbg.run(&mut b93, &mut inp, &mut out, &mut view,
+              &mut StdRng::seed_from_u64(s))?,
+            None => dbg.run(&mut b93, &mut inp, &mut out, &mut view,
+              &mut thread_rng())?,
+        }
+    } else {
+        match seed {
+            Some(s) => run(&mut b93, &mut inp, &mut out,
+              &mut StdRng::seed_from_u64(s), trace, max_steps)?,
+            None => run(&mut b93, &mut inp, &mut out,
+              &mut thread_rng(), trace, max_steps)?,
+        }
+    }
+
+    // freeze the (possibly step-bounded) state so a later --restore can resume
+    if let Some(file) = matches.opt_str("checkpoint") {
+        b93.save(&mut File::create(file)?)?;
+    }
+
+    // a Funge-98 `q` may request a nonzero exit status
+    out.flush()?;
+    let code = b93.return_code();
+    if code != 0 {
+        exit(code);
+    }
+
     Ok(())
 }